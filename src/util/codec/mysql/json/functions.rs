@@ -16,12 +16,25 @@
 
 use std::{u32, char};
 use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::str::Chars;
 use super::super::Result;
 use super::json::Json;
 use super::path_expr::{PathLeg, PathExpression, PATH_EXPR_ASTERISK, PATH_EXPR_ARRAY_INDEX_ASTERISK};
 
 const ESCAPED_UNICODE_BYTES_SIZE: usize = 4;
 
+// ModifyType is the type for JSON modification, used by `Json::modify`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ModifyType {
+    // Insert a new element into a JSON document, only when the element does not exist.
+    Insert,
+    // Replace an old element from a JSON document, only when the element exists.
+    Replace,
+    // Set a new element to a JSON document regardless of whether it exists.
+    Set,
+}
+
 impl Json {
     // extract receives several path expressions as arguments, matches them in j, and returns
     // the target JSON matched any path expressions, which may be autowrapped as an array.
@@ -45,9 +58,186 @@ impl Json {
     pub fn unquote(&self) -> Result<String> {
         match *self {
             Json::String(ref s) => unquote_string(s),
-            _ => Ok(format!("{:?}", self)),
+            _ => Ok(self.to_string()),
+        }
+    }
+
+    // parse ingests a JSON column literal, such as one produced by `to_string`, and builds
+    // the corresponding `Json`.
+    pub fn parse(s: &str) -> Result<Json> {
+        let mut parser = JsonParser::new(s);
+        let j = try!(parser.parse_value());
+        parser.skip_whitespace();
+        if parser.chars.peek().is_some() {
+            return Err(box_err!("Invalid JSON text: unexpected trailing characters"));
+        }
+        Ok(j)
+    }
+
+    // to_string renders `self` as MySQL-compatible canonical JSON text, with object keys
+    // sorted via `get_sorted_keys` for a stable output.
+    pub fn to_string(&self) -> String {
+        let mut buf = String::new();
+        write_json(self, &mut buf);
+        buf
+    }
+
+    // set is used for JSON_SET, it evaluates `path_expr_list` and `values` left to right,
+    // each round setting the addressed element to its paired value against the result of
+    // the previous round, creating the element when it does not yet exist.
+    pub fn set(&self, path_expr_list: &[PathExpression], values: Vec<Json>) -> Result<Json> {
+        self.modify(path_expr_list, values, ModifyType::Set)
+    }
+
+    // insert is used for JSON_INSERT, it is like `set` but never overwrites an element
+    // that already exists.
+    pub fn insert(&self, path_expr_list: &[PathExpression], values: Vec<Json>) -> Result<Json> {
+        self.modify(path_expr_list, values, ModifyType::Insert)
+    }
+
+    // replace is used for JSON_REPLACE, it is like `set` but never creates an element
+    // that does not already exist.
+    pub fn replace(&self, path_expr_list: &[PathExpression], values: Vec<Json>) -> Result<Json> {
+        self.modify(path_expr_list, values, ModifyType::Replace)
+    }
+
+    // remove is used for JSON_REMOVE, it deletes the elements addressed by
+    // `path_expr_list`, evaluated left to right against the result of the previous removal.
+    pub fn remove(&self, path_expr_list: &[PathExpression]) -> Result<Json> {
+        let mut j = self.clone();
+        for path_expr in path_expr_list {
+            if path_expr.legs.is_empty() {
+                return Err(box_err!("Invalid path expression: the path cannot be '$'"));
+            }
+            if let Some(&PathLeg::DoubleAsterisk) = path_expr.legs.last() {
+                return Err(box_err!("Invalid path expression: the path cannot end in '**'"));
+            }
+            j = remove_json(j, path_expr);
+        }
+        Ok(j)
+    }
+
+    fn modify(&self,
+              path_expr_list: &[PathExpression],
+              values: Vec<Json>,
+              mt: ModifyType)
+              -> Result<Json> {
+        if path_expr_list.len() != values.len() {
+            return Err(box_err!("Incorrect number of parameters: {} path expressions, {} values",
+                                 path_expr_list.len(),
+                                 values.len()));
+        }
+        let mut j = self.clone();
+        for (path_expr, value) in path_expr_list.iter().zip(values) {
+            j = set_json(j, path_expr, value, mt);
+        }
+        Ok(j)
+    }
+
+    // merge is used for JSON_MERGE, which MySQL documents as a deprecated synonym of
+    // JSON_MERGE_PRESERVE.
+    pub fn merge(&self, others: &[Json]) -> Json {
+        self.merge_preserve(others)
+    }
+
+    // merge_preserve is used for JSON_MERGE_PRESERVE. Two objects are merged key by key,
+    // recursing into keys present on both sides; everything else (arrays, scalars, and an
+    // object paired with a non-object) is resolved by concatenating into an array, wrapping
+    // scalars into single-element arrays first.
+    pub fn merge_preserve(&self, others: &[Json]) -> Json {
+        let mut merged = self.clone();
+        for other in others {
+            merged = merge_preserve_binary(merged, other.clone());
+        }
+        merged
+    }
+
+    // merge_patch implements RFC 7396 JSON Merge Patch: two objects are merged key by key,
+    // recursing into keys present on both sides; a `Json::None` member in the patch deletes
+    // the matching key from the target, and any other conflict is resolved by the patch
+    // (right-hand) value winning outright.
+    pub fn merge_patch(&self, others: &[Json]) -> Json {
+        let mut merged = self.clone();
+        for other in others {
+            merged = merge_patch_binary(merged, other.clone());
+        }
+        merged
+    }
+
+    // contains is used for JSON_CONTAINS. It tests whether the document addressed by `path`
+    // (or `self`, when `path` is `None`) contains `candidate`: a scalar contains an equal
+    // scalar; an object contains a candidate object when every candidate key exists in the
+    // target and its value is contained; an array contains a candidate array when every
+    // candidate element is contained in some target element, and a scalar candidate is
+    // contained if it equals any array element.
+    pub fn contains(&self, candidate: &Json, path: Option<&PathExpression>) -> bool {
+        let target = match path {
+            Some(p) => {
+                match self.extract(&[p.clone()]) {
+                    Some(t) => t,
+                    None => return false,
+                }
+            }
+            None => self.clone(),
+        };
+        json_contains(&target, candidate)
+    }
+
+    // json_type is used for JSON_TYPE, returning the MySQL type name of `self`.
+    pub fn json_type(&self) -> &'static str {
+        match *self {
+            Json::Object(_) => "OBJECT",
+            Json::Array(_) => "ARRAY",
+            Json::I64(_) => "INTEGER",
+            Json::Double(_) => "DOUBLE",
+            Json::String(_) => "STRING",
+            Json::Boolean(_) => "BOOLEAN",
+            Json::None => "NULL",
+        }
+    }
+
+    // keys is used for JSON_KEYS, returning the sorted keys of the object addressed by
+    // `path` (or `self`, when `path` is `None`) as an `Array`. Returns `None` when the
+    // addressed document is not an object.
+    pub fn keys(&self, path: Option<&PathExpression>) -> Option<Json> {
+        let target = match path {
+            Some(p) => {
+                match self.extract(&[p.clone()]) {
+                    Some(t) => t,
+                    None => return None,
+                }
+            }
+            None => self.clone(),
+        };
+        match target {
+            Json::Object(ref map) => {
+                let sorted_keys = get_sorted_keys(map);
+                Some(Json::Array(sorted_keys.into_iter().map(Json::String).collect()))
+            }
+            _ => None,
         }
     }
+
+    // length is used for JSON_LENGTH, returning the element count of the document
+    // addressed by `path` (or `self`, when `path` is `None`): the number of members for an
+    // object, the number of elements for an array, and 1 for any scalar. Returns `None`
+    // when `path` does not address an element.
+    pub fn length(&self, path: Option<&PathExpression>) -> Option<i64> {
+        let target = match path {
+            Some(p) => {
+                match self.extract(&[p.clone()]) {
+                    Some(t) => t,
+                    None => return None,
+                }
+            }
+            None => self.clone(),
+        };
+        Some(match target {
+            Json::Object(ref map) => map.len() as i64,
+            Json::Array(ref array) => array.len() as i64,
+            _ => 1,
+        })
+    }
 }
 
 // unquote_string recognizes the escape sequences shown in:
@@ -62,27 +252,7 @@ pub fn unquote_string(s: &str) -> Result<String> {
                 Some(c) => c,
                 None => return Err(box_err!("Missing a closing quotation mark in string")),
             };
-            match c {
-                '"' => ret.push('"'),
-                'b' => ret.push('\x08'),
-                'f' => ret.push('\x0C'),
-                'n' => ret.push('\x0A'),
-                'r' => ret.push('\x0D'),
-                't' => ret.push('\x0B'),
-                '\\' => ret.push('\\'),
-                'u' => {
-                    let mut unicode = String::with_capacity(ESCAPED_UNICODE_BYTES_SIZE);
-                    for _ in 0..ESCAPED_UNICODE_BYTES_SIZE {
-                        match chars.next() {
-                            Some(c) => unicode.push(c),
-                            None => return Err(box_err!("Invalid unicode: {}", unicode)),
-                        }
-                    }
-                    let utf8 = try!(decode_escaped_unicode(&unicode));
-                    ret.push(utf8);
-                }
-                _ => ret.push(c),
-            }
+            ret.push(try!(read_escaped_char(c, &mut chars)));
         } else {
             ret.push(ch);
         }
@@ -90,6 +260,34 @@ pub fn unquote_string(s: &str) -> Result<String> {
     Ok(ret)
 }
 
+// read_escaped_char decodes the character following a `\` in a JSON string, as described in:
+// https://dev.mysql.com/doc/refman/5.7/en/json-modification-functions.html#
+// json-unquote-character-escape-sequences
+// It is shared by `unquote_string` and `Json::parse`, consuming further characters from
+// `chars` for the `\uXXXX` form.
+fn read_escaped_char<I: Iterator<Item = char>>(c: char, chars: &mut I) -> Result<char> {
+    Ok(match c {
+        '"' => '"',
+        'b' => '\x08',
+        'f' => '\x0C',
+        'n' => '\x0A',
+        'r' => '\x0D',
+        't' => '\x0B',
+        '\\' => '\\',
+        'u' => {
+            let mut unicode = String::with_capacity(ESCAPED_UNICODE_BYTES_SIZE);
+            for _ in 0..ESCAPED_UNICODE_BYTES_SIZE {
+                match chars.next() {
+                    Some(c) => unicode.push(c),
+                    None => return Err(box_err!("Invalid unicode: {}", unicode)),
+                }
+            }
+            try!(decode_escaped_unicode(&unicode))
+        }
+        _ => c,
+    })
+}
+
 fn decode_escaped_unicode(s: &str) -> Result<char> {
     let u = box_try!(u32::from_str_radix(s, 16));
     char::from_u32(u).ok_or(box_err!("invalid char from: {}", s))
@@ -150,6 +348,439 @@ pub fn extract_json(j: Json, path_expr: &PathExpression) -> Vec<Json> {
     ret
 }
 
+// set_json is used by `Json::set`, `Json::insert`, and `Json::replace`. It walks
+// `path_expr` leg by leg the same way `extract_json` does, but instead of collecting
+// matches it rebuilds the tree with the addressed element modified according to `mt`.
+fn set_json(j: Json, path_expr: &PathExpression, value: Json, mt: ModifyType) -> Json {
+    if path_expr.legs.is_empty() {
+        return match mt {
+            ModifyType::Insert => j,
+            ModifyType::Replace | ModifyType::Set => value,
+        };
+    }
+    let (current_leg, sub_path_expr) = path_expr.pop_one_leg();
+    let last_leg = sub_path_expr.legs.is_empty();
+    match current_leg {
+        PathLeg::Index(i) => {
+            // If j is not an array, autowrap that into array, exactly as `extract_json` does.
+            let mut array = match j {
+                Json::Array(array) => array,
+                other => wrap_to_array(other),
+            };
+            if i == PATH_EXPR_ARRAY_INDEX_ASTERISK {
+                for idx in 0..array.len() {
+                    let child = array.remove(idx);
+                    array.insert(idx, set_json(child, &sub_path_expr, value.clone(), mt));
+                }
+            } else if (i as usize) < array.len() {
+                if !(last_leg && mt == ModifyType::Insert) {
+                    let child = array.remove(i as usize);
+                    array.insert(i as usize, set_json(child, &sub_path_expr, value, mt));
+                }
+            } else if i as usize == array.len() && last_leg && mt != ModifyType::Replace {
+                array.push(value);
+            }
+            Json::Array(array)
+        }
+        PathLeg::Key(key) => {
+            match j {
+                Json::Object(mut map) => {
+                    if key == PATH_EXPR_ASTERISK {
+                        let sorted_keys = get_sorted_keys(&map);
+                        for k in sorted_keys {
+                            let child = map.remove(&k).unwrap();
+                            map.insert(k, set_json(child, &sub_path_expr, value.clone(), mt));
+                        }
+                    } else if map.contains_key(&key) {
+                        if !(last_leg && mt == ModifyType::Insert) {
+                            let child = map.remove(&key).unwrap();
+                            map.insert(key, set_json(child, &sub_path_expr, value, mt));
+                        }
+                    } else if last_leg && mt != ModifyType::Replace {
+                        map.insert(key, value);
+                    }
+                    Json::Object(map)
+                }
+                // Only objects can be addressed by key; anything else is left untouched.
+                other => other,
+            }
+        }
+        // JSON modification functions do not support the '**' path, so just leave the
+        // tree as-is. `Json::remove` rejects a trailing '**' outright; `set`/`insert`/`replace`
+        // return the tree unchanged from a double-asterisk leg downwards.
+        PathLeg::DoubleAsterisk => j,
+    }
+}
+
+// remove_json is used by `Json::remove`. It mirrors `set_json`'s descent but deletes the
+// addressed element instead of replacing it. Unlike `set_json`, it does not autowrap a
+// non-array at an index leg: such a leg removes nothing.
+fn remove_json(j: Json, path_expr: &PathExpression) -> Json {
+    let (current_leg, sub_path_expr) = path_expr.pop_one_leg();
+    let last_leg = sub_path_expr.legs.is_empty();
+    match current_leg {
+        PathLeg::Index(i) => {
+            match j {
+                Json::Array(mut array) => {
+                    if i == PATH_EXPR_ARRAY_INDEX_ASTERISK {
+                        if last_leg {
+                            array.clear();
+                        } else {
+                            array = array.into_iter()
+                                .map(|child| remove_json(child, &sub_path_expr))
+                                .collect();
+                        }
+                    } else if (i as usize) < array.len() {
+                        if last_leg {
+                            array.remove(i as usize);
+                        } else {
+                            let child = array.remove(i as usize);
+                            array.insert(i as usize, remove_json(child, &sub_path_expr));
+                        }
+                    }
+                    Json::Array(array)
+                }
+                other => other,
+            }
+        }
+        PathLeg::Key(key) => {
+            match j {
+                Json::Object(mut map) => {
+                    if key == PATH_EXPR_ASTERISK {
+                        if last_leg {
+                            map.clear();
+                        } else {
+                            let sorted_keys = get_sorted_keys(&map);
+                            for k in sorted_keys {
+                                let child = map.remove(&k).unwrap();
+                                map.insert(k, remove_json(child, &sub_path_expr));
+                            }
+                        }
+                    } else if map.contains_key(&key) {
+                        if last_leg {
+                            map.remove(&key);
+                        } else {
+                            let child = map.remove(&key).unwrap();
+                            map.insert(key, remove_json(child, &sub_path_expr));
+                        }
+                    }
+                    Json::Object(map)
+                }
+                other => other,
+            }
+        }
+        PathLeg::DoubleAsterisk => j,
+    }
+}
+
+// json_contains is used by `Json::contains` to test document containment of `candidate`
+// within `target`.
+fn json_contains(target: &Json, candidate: &Json) -> bool {
+    match (target, candidate) {
+        (&Json::Object(ref t), &Json::Object(ref c)) => {
+            c.iter().all(|(k, cv)| t.get(k).map_or(false, |tv| json_contains(tv, cv)))
+        }
+        (&Json::Array(ref t), &Json::Array(ref c)) => {
+            c.iter().all(|cv| t.iter().any(|tv| json_contains(tv, cv)))
+        }
+        (&Json::Array(ref t), cv) => t.iter().any(|tv| json_contains(tv, cv)),
+        (t, c) => t == c,
+    }
+}
+
+// merge_preserve_binary merges `right` into `left` using the JSON_MERGE_PRESERVE rules.
+fn merge_preserve_binary(left: Json, right: Json) -> Json {
+    match (left, right) {
+        (Json::Object(mut l), Json::Object(r)) => {
+            let sorted_keys = get_sorted_keys(&r);
+            let mut r = r;
+            for k in sorted_keys {
+                let rv = r.remove(&k).unwrap();
+                let merged_v = match l.remove(&k) {
+                    Some(lv) => merge_preserve_binary(lv, rv),
+                    None => rv,
+                };
+                l.insert(k, merged_v);
+            }
+            Json::Object(l)
+        }
+        (Json::Array(mut l), Json::Array(r)) => {
+            l.extend(r);
+            Json::Array(l)
+        }
+        (Json::Array(mut l), r) => {
+            l.push(r);
+            Json::Array(l)
+        }
+        (l, Json::Array(mut r)) => {
+            r.insert(0, l);
+            Json::Array(r)
+        }
+        (l, r) => Json::Array(vec![l, r]),
+    }
+}
+
+// merge_patch_binary merges `right` into `left` using the RFC 7396 JSON Merge Patch rules.
+fn merge_patch_binary(left: Json, right: Json) -> Json {
+    let r = match right {
+        Json::Object(r) => r,
+        _ => return right,
+    };
+    let mut l = match left {
+        Json::Object(l) => l,
+        _ => BTreeMap::new(),
+    };
+    let sorted_keys = get_sorted_keys(&r);
+    let mut r = r;
+    for k in sorted_keys {
+        let rv = r.remove(&k).unwrap();
+        if rv == Json::None {
+            l.remove(&k);
+            continue;
+        }
+        let merged_v = match l.remove(&k) {
+            Some(lv) => merge_patch_binary(lv, rv),
+            None => merge_patch_binary(Json::None, rv),
+        };
+        l.insert(k, merged_v);
+    }
+    Json::Object(l)
+}
+
+// JsonParser is a hand-rolled recursive-descent parser for `Json::parse`, operating
+// directly over a `Peekable<Chars>` since the crate does not otherwise depend on serde.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(s: &'a str) -> JsonParser<'a> {
+        JsonParser { chars: s.chars().peekable() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(box_err!("Expected '{}' but got '{}'", expected, c)),
+            None => Err(box_err!("Unexpected end of JSON text")),
+        }
+    }
+
+    fn consume_literal(&mut self, literal: &str) -> bool {
+        let mut lookahead = self.chars.clone();
+        for expected in literal.chars() {
+            match lookahead.next() {
+                Some(c) if c == expected => continue,
+                _ => return false,
+            }
+        }
+        self.chars = lookahead;
+        true
+    }
+
+    fn parse_value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some(&'{') => self.parse_object(),
+            Some(&'[') => self.parse_array(),
+            Some(&'"') => Ok(Json::String(try!(self.parse_string()))),
+            Some(&'t') | Some(&'f') => self.parse_bool(),
+            Some(&'n') => self.parse_null(),
+            Some(&c) if c == '-' || c.is_digit(10) => self.parse_number(),
+            Some(&c) => Err(box_err!("Unexpected character '{}' in JSON text", c)),
+            None => Err(box_err!("Unexpected end of JSON text")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json> {
+        try!(self.expect('{'));
+        let mut map = BTreeMap::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Ok(Json::Object(map));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = try!(self.parse_string());
+            self.skip_whitespace();
+            try!(self.expect(':'));
+            let value = try!(self.parse_value());
+            map.insert(key, value);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                Some(c) => return Err(box_err!("Expected ',' or '}}' but got '{}'", c)),
+                None => return Err(box_err!("Unexpected end of JSON text")),
+            }
+        }
+        Ok(Json::Object(map))
+    }
+
+    fn parse_array(&mut self) -> Result<Json> {
+        try!(self.expect('['));
+        let mut array = vec![];
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Ok(Json::Array(array));
+        }
+        loop {
+            array.push(try!(self.parse_value()));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => break,
+                Some(c) => return Err(box_err!("Expected ',' or ']' but got '{}'", c)),
+                None => return Err(box_err!("Unexpected end of JSON text")),
+            }
+        }
+        Ok(Json::Array(array))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        try!(self.expect('"'));
+        let mut ret = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => break,
+                Some('\\') => {
+                    let c = match self.chars.next() {
+                        Some(c) => c,
+                        None => return Err(box_err!("Missing a closing quotation mark in string")),
+                    };
+                    ret.push(try!(read_escaped_char(c, &mut self.chars)));
+                }
+                Some(c) => ret.push(c),
+                None => return Err(box_err!("Missing a closing quotation mark in string")),
+            }
+        }
+        Ok(ret)
+    }
+
+    fn parse_bool(&mut self) -> Result<Json> {
+        if self.consume_literal("true") {
+            Ok(Json::Boolean(true))
+        } else if self.consume_literal("false") {
+            Ok(Json::Boolean(false))
+        } else {
+            Err(box_err!("Invalid literal in JSON text"))
+        }
+    }
+
+    fn parse_null(&mut self) -> Result<Json> {
+        if self.consume_literal("null") {
+            Ok(Json::None)
+        } else {
+            Err(box_err!("Invalid literal in JSON text"))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json> {
+        let mut s = String::new();
+        let mut is_double = false;
+        if self.chars.peek() == Some(&'-') {
+            s.push(self.chars.next().unwrap());
+        }
+        self.consume_digits(&mut s);
+        if self.chars.peek() == Some(&'.') {
+            is_double = true;
+            s.push(self.chars.next().unwrap());
+            self.consume_digits(&mut s);
+        }
+        if let Some(&c) = self.chars.peek() {
+            if c == 'e' || c == 'E' {
+                is_double = true;
+                s.push(c);
+                self.chars.next();
+                if let Some(&sign) = self.chars.peek() {
+                    if sign == '+' || sign == '-' {
+                        s.push(sign);
+                        self.chars.next();
+                    }
+                }
+                self.consume_digits(&mut s);
+            }
+        }
+        if is_double {
+            Ok(Json::Double(box_try!(s.parse::<f64>())))
+        } else {
+            Ok(Json::I64(box_try!(s.parse::<i64>())))
+        }
+    }
+
+    fn consume_digits(&mut self, s: &mut String) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_digit(10) {
+                break;
+            }
+            s.push(c);
+            self.chars.next();
+        }
+    }
+}
+
+// write_json serializes `j` into MySQL-compatible canonical JSON text, used by `Json::to_string`.
+fn write_json(j: &Json, buf: &mut String) {
+    match *j {
+        Json::Object(ref map) => {
+            buf.push('{');
+            for (i, key) in get_sorted_keys(map).iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                write_quoted_string(key, buf);
+                buf.push(':');
+                write_json(&map[key], buf);
+            }
+            buf.push('}');
+        }
+        Json::Array(ref array) => {
+            buf.push('[');
+            for (i, elem) in array.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                write_json(elem, buf);
+            }
+            buf.push(']');
+        }
+        Json::String(ref s) => write_quoted_string(s, buf),
+        Json::I64(i) => buf.push_str(&i.to_string()),
+        Json::Double(d) => buf.push_str(&d.to_string()),
+        Json::Boolean(b) => buf.push_str(if b { "true" } else { "false" }),
+        Json::None => buf.push_str("null"),
+    }
+}
+
+fn write_quoted_string(s: &str, buf: &mut String) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\x08' => buf.push_str("\\b"),
+            '\x0C' => buf.push_str("\\f"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if (c as u32) < 0x20 => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
 fn wrap_to_array(j: Json) -> Vec<Json> {
     let mut array = Vec::with_capacity(1);
     array.push(j.clone());
@@ -272,6 +903,270 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_json_set() {
+        let mut test_cases = vec![
+            // Set a new key into an object.
+            (Json::Object(BTreeMap::new()),
+             vec![(PathExpression {
+                       legs: vec![PathLeg::Key(String::from("a"))],
+                       flags: PathExpressionFlag::default(),
+                   },
+                   Json::I64(1))],
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             })),
+            // Set overwrites an existing key.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             vec![(PathExpression {
+                       legs: vec![PathLeg::Key(String::from("a"))],
+                       flags: PathExpressionFlag::default(),
+                   },
+                   Json::I64(2))],
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(2));
+                 m
+             })),
+            // Set appends to an array when the index equals its length.
+            (Json::Array(vec![Json::I64(1)]),
+             vec![(PathExpression {
+                       legs: vec![PathLeg::Index(1)],
+                       flags: PathExpressionFlag::default(),
+                   },
+                   Json::I64(2))],
+             Json::Array(vec![Json::I64(1), Json::I64(2)])),
+            // Set overwrites an existing array element.
+            (Json::Array(vec![Json::I64(1), Json::I64(2)]),
+             vec![(PathExpression {
+                       legs: vec![PathLeg::Index(0)],
+                       flags: PathExpressionFlag::default(),
+                   },
+                   Json::I64(9))],
+             Json::Array(vec![Json::I64(9), Json::I64(2)])),
+        ];
+        for (i, (j, pairs, expected)) in test_cases.drain(..).enumerate() {
+            let exprs: Vec<_> = pairs.iter().map(|&(ref p, _)| p.clone()).collect();
+            let values: Vec<_> = pairs.into_iter().map(|(_, v)| v).collect();
+            let got = j.set(&exprs, values).unwrap();
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_insert() {
+        let mut test_cases = vec![
+            // Insert creates a missing key.
+            (Json::Object(BTreeMap::new()),
+             PathExpression {
+                 legs: vec![PathLeg::Key(String::from("a"))],
+                 flags: PathExpressionFlag::default(),
+             },
+             Json::I64(1),
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             })),
+            // Insert never overwrites an existing key.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             PathExpression {
+                 legs: vec![PathLeg::Key(String::from("a"))],
+                 flags: PathExpressionFlag::default(),
+             },
+             Json::I64(2),
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             })),
+        ];
+        for (i, (j, path, value, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.insert(&[path], vec![value]).unwrap();
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_replace() {
+        let mut test_cases = vec![
+            // Replace never creates a missing key.
+            (Json::Object(BTreeMap::new()),
+             PathExpression {
+                 legs: vec![PathLeg::Key(String::from("a"))],
+                 flags: PathExpressionFlag::default(),
+             },
+             Json::I64(1),
+             Json::Object(BTreeMap::new())),
+            // Replace overwrites an existing key.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             PathExpression {
+                 legs: vec![PathLeg::Key(String::from("a"))],
+                 flags: PathExpressionFlag::default(),
+             },
+             Json::I64(2),
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(2));
+                 m
+             })),
+        ];
+        for (i, (j, path, value, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.replace(&[path], vec![value]).unwrap();
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_remove() {
+        let mut test_cases = vec![
+            // Remove a key from an object.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             PathExpression {
+                 legs: vec![PathLeg::Key(String::from("a"))],
+                 flags: PathExpressionFlag::default(),
+             },
+             Some(Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }))),
+            // Remove an element from an array.
+            (Json::Array(vec![Json::I64(1), Json::I64(2)]),
+             PathExpression {
+                 legs: vec![PathLeg::Index(0)],
+                 flags: PathExpressionFlag::default(),
+             },
+             Some(Json::Array(vec![Json::I64(2)]))),
+            // Removing the root path is rejected.
+            (Json::I64(1),
+             PathExpression {
+                 legs: vec![],
+                 flags: PathExpressionFlag::default(),
+             },
+             None),
+            // Removing a path ending in '**' is rejected.
+            (Json::I64(1),
+             PathExpression {
+                 legs: vec![PathLeg::DoubleAsterisk],
+                 flags: PATH_EXPRESSION_CONTAINS_DOUBLE_ASTERISK,
+             },
+             None),
+        ];
+        for (i, (j, path, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.remove(&[path]);
+            match expected {
+                Some(expected) => {
+                    let got = got.unwrap();
+                    assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+                }
+                None => assert!(got.is_err(), "#{} expected error but got {:?}", i, got),
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_merge_preserve() {
+        let mut test_cases = vec![
+            // object ∘ object: common keys merge recursively, unique keys survive.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             vec![Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(3));
+                 m.insert(String::from("c"), Json::I64(4));
+                 m
+             })],
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::Array(vec![Json::I64(1), Json::I64(3)]));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m.insert(String::from("c"), Json::I64(4));
+                 m
+             })),
+            // array ∘ scalar: the scalar is appended to the array.
+            (Json::Array(vec![Json::I64(1), Json::I64(2)]),
+             vec![Json::I64(3)],
+             Json::Array(vec![Json::I64(1), Json::I64(2), Json::I64(3)])),
+            // scalar ∘ scalar: both wrapped into a single array.
+            (Json::I64(1), vec![Json::I64(2)], Json::Array(vec![Json::I64(1), Json::I64(2)])),
+        ];
+        for (i, (j, others, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.merge_preserve(&others);
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_merge_patch() {
+        let mut test_cases = vec![
+            // object ∘ object: common keys merge recursively, unique keys survive.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             vec![Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(3));
+                 m
+             })],
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(3));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             })),
+            // a `null` member deletes the matching key from the target.
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             vec![Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::None);
+                 m
+             })],
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             })),
+            // a non-object patch replaces the target wholesale.
+            (Json::Object(BTreeMap::new()), vec![Json::I64(1)], Json::I64(1)),
+        ];
+        for (i, (j, others, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.merge_patch(&others);
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
     #[test]
     fn test_decode_escaped_unicode() {
         let mut test_cases = vec![
@@ -327,15 +1222,16 @@ mod test {
             }
         }
 
-        // test unquote other json types
-        let mut test_cases = vec![Json::Object(BTreeMap::new()),
-                                  Json::Array(vec![]),
-                                  Json::I64(2017),
-                                  Json::Double(19.28),
-                                  Json::Boolean(true),
-                                  Json::None];
-        for (i, j) in test_cases.drain(..).enumerate() {
-            let expected = format!("{:?}", j);
+        // test unquote other json types: these now render as real JSON text rather than
+        // the debug representation.
+        let mut test_cases = vec![(Json::Object(BTreeMap::new()), "{}"),
+                                  (Json::Array(vec![]), "[]"),
+                                  (Json::I64(2017), "2017"),
+                                  (Json::Double(19.28), "19.28"),
+                                  (Json::Boolean(true), "true"),
+                                  (Json::None, "null")];
+        for (i, (j, expected)) in test_cases.drain(..).enumerate() {
+            let expected = String::from(expected);
             let r = j.unquote();
             assert!(r.is_ok(), "#{} expect unquote ok but got err {:?}", i, r);
             let got = r.unwrap();
@@ -347,4 +1243,171 @@ mod test {
                        got);
         }
     }
+
+    #[test]
+    fn test_json_parse() {
+        let mut test_cases = vec![
+            ("{}", true, Some(Json::Object(BTreeMap::new()))),
+            ("[]", true, Some(Json::Array(vec![]))),
+            ("null", true, Some(Json::None)),
+            ("true", true, Some(Json::Boolean(true))),
+            ("false", true, Some(Json::Boolean(false))),
+            ("2017", true, Some(Json::I64(2017))),
+            ("-2017", true, Some(Json::I64(-2017))),
+            ("20.08", true, Some(Json::Double(20.08))),
+            ("\"a1\"", true, Some(Json::String(String::from("a1")))),
+            (" { \"a\" : [1, 2.0, \"b\"] } ",
+             true,
+             Some(Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"),
+                          Json::Array(vec![Json::I64(1), Json::Double(2.0), Json::String(String::from("b"))]));
+                 m
+             }))),
+            // invalid input
+            ("", false, None),
+            ("{", false, None),
+            ("truefoo", false, None),
+        ];
+        for (i, (input, no_error, expected)) in test_cases.drain(..).enumerate() {
+            let r = Json::parse(input);
+            if no_error {
+                assert!(r.is_ok(), "#{} expect parse ok but got err {:?}", i, r);
+                let got = r.unwrap();
+                let expected = expected.unwrap();
+                assert_eq!(got,
+                           expected,
+                           "#{} expect {:?} but got {:?}",
+                           i,
+                           expected,
+                           got);
+            } else {
+                assert!(r.is_err(), "#{} expected error but got {:?}", i, r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_to_string() {
+        let mut test_cases = vec![
+            (Json::Object(BTreeMap::new()), "{}"),
+            (Json::Array(vec![]), "[]"),
+            (Json::None, "null"),
+            (Json::Boolean(false), "false"),
+            (Json::I64(2017), "2017"),
+            (Json::Double(20.08), "20.08"),
+            (Json::String(String::from("a1")), "\"a1\""),
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("b"), Json::I64(2));
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             "{\"a\":1,\"b\":2}"),
+        ];
+        for (i, (j, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.to_string();
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_contains() {
+        let mut test_cases = vec![
+            // a scalar contains an equal scalar
+            (Json::I64(1), Json::I64(1), true),
+            (Json::I64(1), Json::I64(2), false),
+            // an object contains a candidate object when every candidate key is contained
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             true),
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(2));
+                 m
+             }),
+             false),
+            // an array contains a candidate array when every candidate element is contained
+            (Json::Array(vec![Json::I64(1), Json::I64(2), Json::I64(3)]),
+             Json::Array(vec![Json::I64(1), Json::I64(3)]),
+             true),
+            // a scalar candidate is contained if it equals any array element
+            (Json::Array(vec![Json::I64(1), Json::I64(2)]), Json::I64(2), true),
+            (Json::Array(vec![Json::I64(1), Json::I64(2)]), Json::I64(3), false),
+        ];
+        for (i, (target, candidate, expected)) in test_cases.drain(..).enumerate() {
+            let got = target.contains(&candidate, None);
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_type() {
+        let mut test_cases = vec![
+            (Json::Object(BTreeMap::new()), "OBJECT"),
+            (Json::Array(vec![]), "ARRAY"),
+            (Json::I64(1), "INTEGER"),
+            (Json::Double(1.0), "DOUBLE"),
+            (Json::String(String::from("a")), "STRING"),
+            (Json::Boolean(true), "BOOLEAN"),
+            (Json::None, "NULL"),
+        ];
+        for (i, (j, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.json_type();
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_keys() {
+        let mut test_cases = vec![
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("b"), Json::I64(2));
+                 m.insert(String::from("a"), Json::I64(1));
+                 m
+             }),
+             Some(Json::Array(vec![Json::String(String::from("a")), Json::String(String::from("b"))]))),
+            (Json::Array(vec![]), None),
+            (Json::I64(1), None),
+        ];
+        for (i, (j, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.keys(None);
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
+
+    #[test]
+    fn test_json_length() {
+        let mut test_cases = vec![
+            (Json::Object({
+                 let mut m = BTreeMap::new();
+                 m.insert(String::from("a"), Json::I64(1));
+                 m.insert(String::from("b"), Json::I64(2));
+                 m
+             }),
+             Some(2)),
+            (Json::Array(vec![Json::I64(1), Json::I64(2), Json::I64(3)]), Some(3)),
+            (Json::I64(1), Some(1)),
+            (Json::None, Some(1)),
+        ];
+        for (i, (j, expected)) in test_cases.drain(..).enumerate() {
+            let got = j.length(None);
+            assert_eq!(got, expected, "#{} expect {:?}, but got {:?}", i, expected, got);
+        }
+    }
 }
@@ -14,24 +14,38 @@
 use kvproto::raft_serverpb::RaftMessage;
 use tikv::raftstore::Result;
 use tikv::raftstore::store::Transport;
-use rand;
-use std::sync::{Arc, RwLock};
+use rand::{self, Rng, SeedableRng, XorShiftRng};
+use std::sync::{Arc, Mutex, RwLock};
 
 use super::util::*;
 use self::Strategy::*;
 
+// The seed is fixed so that a flaky test caused by a particular reordering can be
+// reproduced by rerunning it.
+const OUT_OF_ORDER_SEED: [u32; 4] = [0x5216af45, 0x1054f4d1, 0x5be85141, 0x3c3c3c3c];
+
 #[derive(Clone)]
 pub enum Strategy {
     DropPacket(u32),
     Delay(u64),
-    OutOfOrder,
+    // OutOfOrder buffers messages and flushes them back, shuffled, once the buffer holds
+    // this many of them.
+    OutOfOrder(usize),
+    // Partition splits the cluster into the two given groups of store ids and drops any
+    // message crossing between them, simulating a network partition.
+    Partition(Vec<u64>, Vec<u64>),
+    // Isolate blocks all traffic to and from the given store id.
+    Isolate(u64),
 }
 
-trait Filter: Send + Sync {
+trait Filter<T: Transport>: Send + Sync {
     // in a SimulateTransport, if any filter's before return true, msg will be discard
     fn before(&self, msg: &RaftMessage) -> bool;
     // with after provided, one can change the return value arbitrarily
     fn after(&self, Result<()>) -> Result<()>;
+    // flush gives a filter a chance to re-emit messages it previously held onto, once
+    // `trans` is available again; most filters never hold onto anything.
+    fn flush(&self, _trans: &T) {}
 }
 
 struct FilterDropPacket {
@@ -42,9 +56,23 @@ struct FilterDelay {
     duration: u64,
 }
 
-struct FilterOutOfOrder;
+struct FilterOutOfOrder {
+    batch_size: usize,
+    buffer: Mutex<Vec<RaftMessage>>,
+    rng: Mutex<XorShiftRng>,
+}
+
+impl FilterOutOfOrder {
+    fn new(batch_size: usize) -> FilterOutOfOrder {
+        FilterOutOfOrder {
+            batch_size: batch_size,
+            buffer: Mutex::new(vec![]),
+            rng: Mutex::new(XorShiftRng::from_seed(OUT_OF_ORDER_SEED)),
+        }
+    }
+}
 
-impl Filter for FilterDropPacket {
+impl<T: Transport> Filter<T> for FilterDropPacket {
     fn before(&self, _: &RaftMessage) -> bool {
         rand::random::<u32>() % 100u32 < self.rate
     }
@@ -53,7 +81,7 @@ impl Filter for FilterDropPacket {
     }
 }
 
-impl Filter for FilterDelay {
+impl<T: Transport> Filter<T> for FilterDelay {
     fn before(&self, _: &RaftMessage) -> bool {
         sleep_ms(self.duration);
         false
@@ -63,49 +91,108 @@ impl Filter for FilterDelay {
     }
 }
 
-impl Filter for FilterOutOfOrder {
-    fn before(&self, _: &RaftMessage) -> bool {
-        unimplemented!()
+struct FilterPartition {
+    s1: Vec<u64>,
+    s2: Vec<u64>,
+}
+
+struct FilterIsolate {
+    store_id: u64,
+}
+
+impl<T: Transport> Filter<T> for FilterPartition {
+    fn before(&self, msg: &RaftMessage) -> bool {
+        let from = msg.get_from_peer().get_store_id();
+        let to = msg.get_to_peer().get_store_id();
+        (self.s1.contains(&from) && self.s2.contains(&to)) ||
+        (self.s2.contains(&from) && self.s1.contains(&to))
+    }
+    fn after(&self, x: Result<()>) -> Result<()> {
+        x
+    }
+}
+
+impl<T: Transport> Filter<T> for FilterIsolate {
+    fn before(&self, msg: &RaftMessage) -> bool {
+        msg.get_from_peer().get_store_id() == self.store_id ||
+        msg.get_to_peer().get_store_id() == self.store_id
+    }
+    fn after(&self, x: Result<()>) -> Result<()> {
+        x
+    }
+}
+
+impl<T: Transport> Filter<T> for FilterOutOfOrder {
+    fn before(&self, msg: &RaftMessage) -> bool {
+        // Stash the message instead of sending it straight through; `flush` re-emits
+        // everything currently buffered, shuffled, once enough of them have piled up.
+        self.buffer.lock().unwrap().push(msg.clone());
+        true
     }
     fn after(&self, _: Result<()>) -> Result<()> {
-        unimplemented!()
+        // `before` always discards, so `send` never reaches the real transport on this
+        // path; `flush` sends buffered messages later and ignores their results.
+        Ok(())
+    }
+    fn flush(&self, trans: &T) {
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() < self.batch_size {
+            return;
+        }
+        self.rng.lock().unwrap().shuffle(&mut buffer);
+        for msg in buffer.drain(..) {
+            // Best effort: out-of-order delivery does not guarantee the message arrives.
+            let _ = trans.send(msg);
+        }
     }
 }
 
 pub struct SimulateTransport<T: Transport> {
-    filters: Vec<Box<Filter>>,
+    // Filters are kept behind a `RwLock` rather than built once, because a running
+    // cluster needs to be able to form and later heal a partition without rebuilding
+    // the transport.
+    filters: RwLock<Vec<Box<Filter<T>>>>,
     trans: Arc<RwLock<T>>,
 }
 
 impl<T: Transport> SimulateTransport<T> {
     pub fn new(strategy: Vec<Strategy>, trans: Arc<RwLock<T>>) -> SimulateTransport<T> {
-        let mut filters: Vec<Box<Filter>> = vec![];
+        let st = SimulateTransport {
+            filters: RwLock::new(vec![]),
+            trans: trans,
+        };
         for s in strategy {
-            match s {
-                DropPacket(rate) => {
-                    filters.push(box FilterDropPacket { rate: rate });
-                }
-                Delay(latency) => {
-                    filters.push(box FilterDelay { duration: latency });
-                }
-                OutOfOrder => {
-                    filters.push(box FilterOutOfOrder);
-                }
-            }
+            st.add_filter(new_filter(s));
         }
+        st
+    }
 
-        SimulateTransport {
-            filters: filters,
-            trans: trans,
-        }
+    pub fn add_filter(&self, filter: Box<Filter<T>>) {
+        self.filters.write().unwrap().push(filter);
+    }
+
+    pub fn clear_filters(&self) {
+        self.filters.write().unwrap().clear();
+    }
+}
+
+fn new_filter<T: Transport>(s: Strategy) -> Box<Filter<T>> {
+    match s {
+        DropPacket(rate) => box FilterDropPacket { rate: rate },
+        Delay(latency) => box FilterDelay { duration: latency },
+        OutOfOrder(batch_size) => box FilterOutOfOrder::new(batch_size),
+        Partition(s1, s2) => box FilterPartition { s1: s1, s2: s2 },
+        Isolate(store_id) => box FilterIsolate { store_id: store_id },
     }
 }
 
 impl<T: Transport> Transport for SimulateTransport<T> {
     fn send(&self, msg: RaftMessage) -> Result<()> {
+        let filters = self.filters.read().unwrap();
+
         let mut discard = false;
-        for strategy in &self.filters {
-            if strategy.before(&msg) {
+        for filter in filters.iter() {
+            if filter.before(&msg) {
                 discard = true;
             }
         }
@@ -115,8 +202,15 @@ impl<T: Transport> Transport for SimulateTransport<T> {
             res = self.trans.read().unwrap().send(msg);
         }
 
-        for strategy in self.filters.iter().rev() {
-            res = strategy.after(res);
+        for filter in filters.iter().rev() {
+            res = filter.after(res);
+        }
+
+        {
+            let trans = self.trans.read().unwrap();
+            for filter in filters.iter() {
+                filter.flush(&*trans);
+            }
         }
 
         res